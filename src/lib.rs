@@ -6,6 +6,12 @@ mod local;
 #[cfg(feature = "redis")]
 mod redis;
 
+#[cfg(feature = "postgres")]
+mod postgres;
+
+#[cfg(feature = "crypto")]
+mod transform;
+
 pub use local::LocalStash;
 #[cfg(feature = "redis")]
 pub use redis::{
@@ -13,6 +19,18 @@ pub use redis::{
     RedisError,
     RedisStash,
 };
+#[cfg(feature = "postgres")]
+pub use postgres::{
+    PostgresError,
+    PostgresStash,
+};
+#[cfg(feature = "crypto")]
+pub use transform::{
+    AeadEncryption,
+    GzipCompression,
+    Transform,
+    TransformStash,
+};
 
 use thiserror::Error;
 
@@ -23,6 +41,12 @@ pub enum StashError {
     InvalidKey(String),
     #[error("Backend error: {0}")]
     BackendError(#[from] Box<dyn core::error::Error + Send + Sync>),
+    #[cfg(feature = "serde")]
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[cfg(feature = "redis")]
+    #[error("Circuit breaker open: too many recent backend connection failures")]
+    CircuitOpen,
 }
 
 impl StashError {
@@ -77,8 +101,137 @@ pub trait Stash: Send + Sync {
         K: AsRef<str> + Send + Sync,
     ;
 
+    /// Gets many values from the stash in a single round trip.
+    ///
+    /// The returned [`Vec`] preserves the order of `keys`; each element is
+    /// `None` if the corresponding key was not present in the stash.
+    async fn fetch_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<Vec<Option<String>>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    ;
+
+    /// Sets many values within the stash in a single round trip.
+    ///
+    /// Unlike [`Stash::stash`], this does not return previous values, since
+    /// the underlying batch commands (e.g. Redis' `MSET`) do not report them.
+    ///
+    /// Every `key` is validated before any value is written, so a single
+    /// invalid key fails the whole batch without issuing a partial write.
+    ///
+    /// # Naming Convention
+    /// See [`Stash::stash`].
+    async fn stash_many<K, V, I>(
+        &self,
+        entries: I,
+    ) -> Result<(), StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+        I: IntoIterator<Item = (K, V)> + Send,
+        I::IntoIter: Send,
+    ;
+
+    /// Deletes many entries from the stash in a single round trip.
+    async fn delete_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<(), StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    ;
+
+    /// Sets a value within the stash that automatically expires after `ttl`
+    /// has elapsed, and returns the previous value (if there was one).
+    ///
+    /// This is useful for session/token use cases, e.g. `session:f05a29`.
+    ///
+    /// # Naming Convention
+    /// See [`Stash::stash`].
+    async fn stash_with_ttl<K, V>(
+        &self,
+        key: K,
+        value: V,
+        ttl: core::time::Duration,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+    ;
+
+    /// Returns the remaining time-to-live of `key`, or `None` if `key` does
+    /// not exist or does not expire.
+    async fn ttl<K>(
+        &self,
+        key: K,
+    ) -> Result<Option<core::time::Duration>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+    ;
+
+    /// Lists all keys stored under `prefix`.
+    ///
+    /// Since keys are colon-delimited namespaces (e.g. `user:123:name`),
+    /// this allows enumerating an entire namespace, e.g. `user:123:` lists
+    /// every key belonging to user `123`.
+    async fn keys_with_prefix<P>(
+        &self,
+        prefix: P,
+    ) -> Result<Vec<String>, StashError>
+    where
+        P: AsRef<str> + Send + Sync,
+    ;
+
+    /// Serializes `value` to JSON and stashes it, returning the previous
+    /// value deserialized back to `T` (if there was one and it deserializes
+    /// cleanly).
+    ///
+    /// # Naming Convention
+    /// See [`Stash::stash`].
+    #[cfg(feature = "serde")]
+    async fn stash_typed<K, T>(
+        &self,
+        key: K,
+        value: &T,
+    ) -> Result<Option<T>, StashError>
+    where
+        K: Into<String> + Send + Sync,
+        T: serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+    {
+        let value = serde_json::to_string(value)?;
+        match self.stash(key, value).await? {
+            // The write already succeeded by this point, so a previous value
+            // that fails to deserialize (e.g. it predates a type change)
+            // must not turn the whole call into an error.
+            Some(previous) => Ok(serde_json::from_str(&previous).ok()),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches a value from the stash and deserializes it from JSON into `T`.
+    #[cfg(feature = "serde")]
+    async fn fetch_typed<K, T>(
+        &self,
+        key: K,
+    ) -> Result<Option<T>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        T: serde::de::DeserializeOwned + Send + Sync,
+    {
+        match self.fetch(key).await? {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
     /// Validates a stash key.
-    /// 
+    ///
     /// This should be called internally by types that implement [`Stash`].
     #[inline]
     fn validate_key(
@@ -98,4 +251,31 @@ pub trait Stash: Send + Sync {
         }
         Ok(())
     }
+
+    /// Validates a key prefix, as passed to [`Stash::keys_with_prefix`].
+    ///
+    /// This follows the same rules as [`Stash::validate_key`], except a
+    /// single trailing `:` is allowed (denoting "everything under this
+    /// namespace") and the prefix isn't required to be a complete key.
+    ///
+    /// This should be called internally by types that implement [`Stash`].
+    #[inline]
+    fn validate_prefix(
+        prefix: &str,
+    ) -> Result<(), StashError> {
+        if prefix.is_empty() {
+            return Err(StashError::InvalidKey("Prefix must not be empty".into()));
+        }
+        if prefix.starts_with(':') {
+            return Err(StashError::InvalidKey("Prefix must not start with ':'".into()));
+        }
+        let body = prefix.strip_suffix(':').unwrap_or(prefix);
+        if body.is_empty() || body.split(':').any(|segment| segment.is_empty()) {
+            return Err(StashError::InvalidKey("Prefix must not contain empty segments".into()));
+        }
+        if !prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == ':' || c == '_') {
+            return Err(StashError::InvalidKey("Prefix contains invalid characters".into()));
+        }
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,15 +1,36 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use tokio::sync::Mutex;
 
 use crate::{Stash, StashError};
 
+/// An entry stored within a [`LocalStash`], paired with an optional absolute
+/// expiry instant.
+type Entry = (String, Option<Instant>);
+
+/// The number of shards a [`LocalStash`] splits its entries across.
+///
+/// Each shard has its own lock, so operations on keys that happen to land in
+/// different shards can proceed concurrently instead of contending for a
+/// single lock over the whole map.
+const SHARD_COUNT: usize = 16;
+
+/// A single shard of a [`LocalStash`].
+type Shard = Mutex<HashMap<String, Entry>>;
+
 /// A local in-memory [`Stash`].
-/// 
-/// This is essentially just a wrapper around [`HashMap`] that implements
-/// [`Stash`] and can be shared across threads.
+///
+/// Entries are spread across [`SHARD_COUNT`] independently-locked
+/// [`HashMap`]s (by hash of the key) to reduce lock contention under
+/// concurrent access, while still being [`Clone`]-able and shareable across
+/// threads.
 #[derive(Clone)]
-pub struct LocalStash(Arc<Mutex<HashMap<String, String>>>);
+pub struct LocalStash(Arc<[Shard; SHARD_COUNT]>);
 
 impl Default for LocalStash {
     fn default() -> Self {
@@ -22,22 +43,108 @@ impl LocalStash {
     #[inline(always)]
     #[must_use]
     pub fn new() -> Self {
-        Self(Default::default())
+        Self(Arc::new(std::array::from_fn(|_| Mutex::new(HashMap::new()))))
     }
 
     /// Returns `true` if the [`LocalStash`] is empty, otherwise returns
     /// `false`.
-    #[inline(always)]
+    ///
+    /// Expired entries are purged before the check so they are not counted.
     #[must_use]
     pub async fn is_empty(&self) -> bool {
-        self.0.lock().await.is_empty()
+        for shard in self.0.iter() {
+            let mut map = shard.lock().await;
+            Self::purge_expired(&mut map);
+            if !map.is_empty() {
+                return false;
+            }
+        }
+        true
     }
 
     /// Returns the number of stashed keys in the [`LocalStash`].
-    #[inline(always)]
+    ///
+    /// Expired entries are purged before counting so they are not included.
     #[must_use]
     pub async fn len(&self) -> usize {
-        self.0.lock().await.len()
+        let mut len = 0;
+        for shard in self.0.iter() {
+            let mut map = shard.lock().await;
+            Self::purge_expired(&mut map);
+            len += map.len();
+        }
+        len
+    }
+
+    /// Spawns a background task that periodically purges expired entries
+    /// from the stash, so memory doesn't grow unbounded with short-lived
+    /// entries that are never read after they expire.
+    ///
+    /// Running a sweeper is optional: expired entries are always purged
+    /// lazily as they're touched by [`fetch`](Stash::fetch),
+    /// [`stash`](Stash::stash), [`delete`](Stash::delete), [`len`](Self::len),
+    /// and [`is_empty`](Self::is_empty).
+    #[must_use]
+    pub fn spawn_sweeper(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let stash = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                for shard in stash.0.iter() {
+                    let mut map = shard.lock().await;
+                    Self::purge_expired(&mut map);
+                }
+            }
+        })
+    }
+
+    /// Returns the index of the shard that `key` is stored in.
+    #[inline]
+    fn shard_index(&self, key: &str) -> usize {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() as usize) % SHARD_COUNT
+    }
+
+    /// Returns the shard that `key` is stored in.
+    #[inline]
+    fn shard(&self, key: &str) -> &Shard {
+        &self.0[self.shard_index(key)]
+    }
+
+    /// Locks every shard in `indices` (deduplicated) up front, before any of
+    /// them are read or mutated, so that a batch operation spanning several
+    /// shards is atomic with respect to concurrent single-key and batch
+    /// operations. [`len`](Self::len), [`is_empty`](Self::is_empty) and
+    /// [`keys_with_prefix`](Stash::keys_with_prefix) lock one shard at a
+    /// time, so they can observe a batch that is only partly applied.
+    ///
+    /// Shards are always locked in index order, regardless of the order
+    /// `indices` are given in, to avoid deadlocking against another batch
+    /// operation locking the same shards in a different order.
+    async fn lock_shards(&self, indices: &[usize]) -> Vec<Option<tokio::sync::MutexGuard<'_, HashMap<String, Entry>>>> {
+        let mut sorted = indices.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        let mut guards: Vec<Option<tokio::sync::MutexGuard<'_, HashMap<String, Entry>>>> =
+            (0..SHARD_COUNT).map(|_| None).collect();
+        for index in sorted {
+            guards[index] = Some(self.0[index].lock().await);
+        }
+        guards
+    }
+
+    /// Returns `true` if `entry` has not yet expired as of `now`.
+    #[inline]
+    fn is_live(entry: &Entry, now: Instant) -> bool {
+        entry.1.is_none_or(|expiry| expiry > now)
+    }
+
+    /// Removes every entry from `map` that has expired.
+    fn purge_expired(map: &mut HashMap<String, Entry>) {
+        let now = Instant::now();
+        map.retain(|_, entry| Self::is_live(entry, now));
     }
 }
 
@@ -50,13 +157,15 @@ impl Stash for LocalStash {
     where
         K: AsRef<str> + Send + Sync,
     {
-        Ok(
-            self.0
-                .lock()
-                .await
-                .get(key.as_ref())
-                .map(|value| value.to_owned())
-        )
+        let mut map = self.shard(key.as_ref()).lock().await;
+        match map.get(key.as_ref()) {
+            Some(entry) if Self::is_live(entry, Instant::now()) => Ok(Some(entry.0.clone())),
+            Some(_) => {
+                map.remove(key.as_ref());
+                Ok(None)
+            },
+            None => Ok(None),
+        }
     }
 
     async fn stash<K, V>(
@@ -70,11 +179,12 @@ impl Stash for LocalStash {
     {
         let key = Into::<String>::into(key);
         Self::validate_key(&key)?;
+        let mut map = self.shard(&key).lock().await;
+        let now = Instant::now();
         Ok(
-            self.0
-                .lock()
-                .await
-                .insert(key, value.into())
+            map.insert(key, (value.into(), None))
+                .filter(|previous| Self::is_live(previous, now))
+                .map(|previous| previous.0)
         )
     }
 
@@ -85,17 +195,149 @@ impl Stash for LocalStash {
     where
         K: AsRef<str> + Send + Sync,
     {
+        let mut map = self.shard(key.as_ref()).lock().await;
+        let now = Instant::now();
+        Ok(
+            map.remove(key.as_ref())
+                .filter(|previous| Self::is_live(previous, now))
+                .map(|previous| previous.0)
+        )
+    }
+
+    async fn fetch_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<Vec<Option<String>>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    {
+        let keys: Vec<String> = keys.into_iter().map(|key| key.as_ref().to_owned()).collect();
+        let shard_indices: Vec<usize> = keys.iter().map(|key| self.shard_index(key)).collect();
+        let guards = self.lock_shards(&shard_indices).await;
+
+        let now = Instant::now();
+        Ok(
+            keys.iter()
+                .zip(&shard_indices)
+                .map(|(key, &shard_index)| {
+                    guards[shard_index].as_ref().unwrap()
+                        .get(key)
+                        .filter(|entry| Self::is_live(entry, now))
+                        .map(|entry| entry.0.clone())
+                })
+                .collect()
+        )
+    }
+
+    async fn stash_many<K, V, I>(
+        &self,
+        entries: I,
+    ) -> Result<(), StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+        I: IntoIterator<Item = (K, V)> + Send,
+        I::IntoIter: Send,
+    {
+        let entries: Vec<(String, String)> = entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        for (key, _) in &entries {
+            Self::validate_key(key)?;
+        }
+
+        let shard_indices: Vec<usize> = entries.iter().map(|(key, _)| self.shard_index(key)).collect();
+        let mut guards = self.lock_shards(&shard_indices).await;
+        for ((key, value), shard_index) in entries.into_iter().zip(shard_indices) {
+            guards[shard_index].as_mut().unwrap().insert(key, (value, None));
+        }
+        Ok(())
+    }
+
+    async fn delete_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<(), StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    {
+        let keys: Vec<String> = keys.into_iter().map(|key| key.as_ref().to_owned()).collect();
+        let shard_indices: Vec<usize> = keys.iter().map(|key| self.shard_index(key)).collect();
+        let mut guards = self.lock_shards(&shard_indices).await;
+        for (key, shard_index) in keys.into_iter().zip(shard_indices) {
+            guards[shard_index].as_mut().unwrap().remove(&key);
+        }
+        Ok(())
+    }
+
+    async fn stash_with_ttl<K, V>(
+        &self,
+        key: K,
+        value: V,
+        ttl: Duration,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+    {
+        let key = Into::<String>::into(key);
+        Self::validate_key(&key)?;
+        let mut map = self.shard(&key).lock().await;
+        let now = Instant::now();
         Ok(
-            self.0
-                .lock()
-                .await
-                .remove(key.as_ref())
+            map.insert(key, (value.into(), Some(now + ttl)))
+                .filter(|previous| Self::is_live(previous, now))
+                .map(|previous| previous.0)
         )
     }
+
+    async fn ttl<K>(
+        &self,
+        key: K,
+    ) -> Result<Option<Duration>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+    {
+        let mut map = self.shard(key.as_ref()).lock().await;
+        let now = Instant::now();
+        match map.get(key.as_ref()) {
+            Some(entry) if Self::is_live(entry, now) => Ok(entry.1.map(|expiry| expiry - now)),
+            Some(_) => {
+                map.remove(key.as_ref());
+                Ok(None)
+            },
+            None => Ok(None),
+        }
+    }
+
+    async fn keys_with_prefix<P>(
+        &self,
+        prefix: P,
+    ) -> Result<Vec<String>, StashError>
+    where
+        P: AsRef<str> + Send + Sync,
+    {
+        let prefix = prefix.as_ref();
+        Self::validate_prefix(prefix)?;
+        let mut keys = Vec::new();
+        for shard in self.0.iter() {
+            let mut map = shard.lock().await;
+            Self::purge_expired(&mut map);
+            keys.extend(map.keys().filter(|key| key.starts_with(prefix)).cloned());
+        }
+        Ok(keys)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::{LocalStash, Stash};
 
     #[tokio::test]
@@ -144,4 +386,123 @@ mod tests {
         assert_eq!(stash.delete("key1").await.unwrap(), Some("1".to_owned()));
         assert_eq!(stash.delete("key2").await.unwrap(), Some("2".to_owned()));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn fetch_stash_delete_many() {
+        let stash = LocalStash::new();
+        stash.stash_many([
+            ("user:1:name", "Alice"),
+            ("user:2:name", "Bob"),
+            ("user:3:name", "Charlie"),
+        ]).await.unwrap();
+        assert_eq!(stash.len().await, 3);
+
+        assert_eq!(
+            stash.fetch_many(["user:1:name", "user:2:name", "missing", "user:3:name"]).await.unwrap(),
+            vec![
+                Some("Alice".to_owned()),
+                Some("Bob".to_owned()),
+                None,
+                Some("Charlie".to_owned()),
+            ],
+        );
+
+        stash.delete_many(["user:1:name", "user:3:name"]).await.unwrap();
+        assert_eq!(stash.len().await, 1);
+        assert_eq!(stash.fetch("user:2:name").await.unwrap(), Some("Bob".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn invalid_stash_many_key_rejects_whole_batch() {
+        let stash = LocalStash::new();
+        assert!(stash.stash_many([("valid:key", "1"), ("invalid key", "2")]).await.is_err());
+        assert!(stash.is_empty().await);
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn stash_and_fetch_typed() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Token {
+            subject: String,
+            expiry: u64,
+        }
+
+        let stash = LocalStash::new();
+        let token = Token { subject: "alice".into(), expiry: 1234 };
+        assert_eq!(stash.stash_typed("session:f05a29", &token).await.unwrap(), None);
+        assert_eq!(stash.fetch_typed("session:f05a29").await.unwrap(), Some(token));
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn stash_typed_overwrite_of_non_matching_previous_value_still_succeeds() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Token {
+            subject: String,
+        }
+
+        let stash = LocalStash::new();
+        stash.stash("session:f05a29", "not json").await.unwrap();
+        let token = Token { subject: "alice".into() };
+        assert_eq!(stash.stash_typed("session:f05a29", &token).await.unwrap(), None);
+        assert_eq!(stash.fetch_typed("session:f05a29").await.unwrap(), Some(token));
+    }
+
+    #[tokio::test]
+    async fn stash_with_ttl_expires() {
+        let stash = LocalStash::new();
+        stash.stash_with_ttl("session:f05a29", "token", Duration::from_millis(20)).await.unwrap();
+        assert_eq!(stash.fetch("session:f05a29").await.unwrap(), Some("token".to_owned()));
+        assert!(stash.ttl("session:f05a29").await.unwrap().is_some());
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+
+        assert_eq!(stash.fetch("session:f05a29").await.unwrap(), None);
+        assert_eq!(stash.ttl("session:f05a29").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn ttl_is_none_for_entries_without_expiry() {
+        let stash = LocalStash::new();
+        stash.stash("key", "value").await.unwrap();
+        assert_eq!(stash.ttl("key").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn keys_with_prefix_lists_namespace() {
+        let stash = LocalStash::new();
+        stash.stash_many([
+            ("user:1:name", "Alice"),
+            ("user:1:email", "alice@example.com"),
+            ("user:2:name", "Bob"),
+        ]).await.unwrap();
+
+        let mut keys = stash.keys_with_prefix("user:1:").await.unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["user:1:email".to_owned(), "user:1:name".to_owned()]);
+    }
+
+    #[tokio::test]
+    async fn invalid_prefix_is_rejected() {
+        let stash = LocalStash::new();
+        assert!(stash.keys_with_prefix("user:1:*").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn sweeper_purges_expired_entries() {
+        let stash = LocalStash::new();
+        stash.stash_with_ttl("session:f05a29", "token", Duration::from_millis(20)).await.unwrap();
+        let sweeper = stash.spawn_sweeper(Duration::from_millis(10));
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        // Inspect the shards directly: `is_empty` purges expired entries on
+        // its own, so it alone would not show that the sweeper ran.
+        for shard in stash.0.iter() {
+            assert!(shard.lock().await.is_empty());
+        }
+        assert!(stash.is_empty().await);
+
+        sweeper.abort();
+    }
+}
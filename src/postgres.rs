@@ -0,0 +1,276 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tokio_postgres::{Client, NoTls};
+
+use crate::{Stash, StashError};
+
+pub use tokio_postgres::Error as PostgresError;
+
+impl From<PostgresError> for StashError {
+    fn from(error: PostgresError) -> Self {
+        Self::backend(error)
+    }
+}
+
+/// [`Stash`] backed by a PostgreSQL table, giving a durable, transactional
+/// stash without running Redis.
+///
+/// Entries are stored in a `(key TEXT PRIMARY KEY, value TEXT, expiry
+/// BIGINT)` table, where `expiry` is a Unix timestamp (in seconds) after
+/// which the entry is considered expired, or `NULL` if the entry never
+/// expires.
+#[derive(Clone)]
+pub struct PostgresStash(Arc<Client>);
+
+impl PostgresStash {
+    /// Connects to a PostgreSQL server and returns a new [`PostgresStash`],
+    /// creating the backing table if it doesn't already exist.
+    pub async fn connect<T: Into<String>>(connection_string: T) -> Result<Self, PostgresError> {
+        let (client, connection) = tokio_postgres::connect(
+            &connection_string.into(),
+            NoTls,
+        ).await?;
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+        client.batch_execute(
+            "CREATE TABLE IF NOT EXISTS stash (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                expiry BIGINT
+            )"
+        ).await?;
+        Ok(Self(Arc::new(client)))
+    }
+
+    /// Returns the current Unix timestamp, in seconds.
+    #[inline]
+    fn now_epoch() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is before the Unix epoch")
+            .as_secs() as i64
+    }
+
+    /// Escapes `prefix` for safe use as a `LIKE` pattern, then appends `%` so
+    /// it matches every key under that namespace.
+    #[inline]
+    fn like_prefix_pattern(prefix: &str) -> String {
+        let mut pattern = prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        pattern.push('%');
+        pattern
+    }
+}
+
+#[async_trait::async_trait]
+impl Stash for PostgresStash {
+    async fn fetch<K>(
+        &self,
+        key: K,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+    {
+        let now = Self::now_epoch();
+        let row = self.0.query_opt(
+            "SELECT value FROM stash WHERE key = $1 AND (expiry IS NULL OR expiry > $2)",
+            &[&key.as_ref(), &now],
+        ).await?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    async fn stash<K, V>(
+        &self,
+        key: K,
+        value: V,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+    {
+        let key = Into::<String>::into(key);
+        Self::validate_key(&key)?;
+        let value = Into::<String>::into(value);
+        let now = Self::now_epoch();
+        let row = self.0.query_one(
+            "WITH previous AS (
+                SELECT value FROM stash WHERE key = $1 AND (expiry IS NULL OR expiry > $3)
+            )
+            INSERT INTO stash (key, value, expiry) VALUES ($1, $2, NULL)
+            ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expiry = NULL
+            RETURNING (SELECT value FROM previous)",
+            &[&key, &value, &now],
+        ).await?;
+        Ok(row.get(0))
+    }
+
+    async fn delete<K>(
+        &self,
+        key: K,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+    {
+        let now = Self::now_epoch();
+        let row = self.0.query_opt(
+            "DELETE FROM stash WHERE key = $1 RETURNING value, expiry",
+            &[&key.as_ref()],
+        ).await?;
+        Ok(
+            row.and_then(|row| {
+                let expiry: Option<i64> = row.get(1);
+                if expiry.is_none_or(|expiry| expiry > now) {
+                    Some(row.get(0))
+                } else {
+                    None
+                }
+            })
+        )
+    }
+
+    async fn fetch_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<Vec<Option<String>>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    {
+        let keys: Vec<String> = keys.into_iter().map(|key| key.as_ref().to_owned()).collect();
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let now = Self::now_epoch();
+        let rows = self.0.query(
+            "SELECT key, value FROM stash WHERE key = ANY($1) AND (expiry IS NULL OR expiry > $2)",
+            &[&keys, &now],
+        ).await?;
+        let values: HashMap<String, String> = rows
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect();
+        Ok(keys.iter().map(|key| values.get(key).cloned()).collect())
+    }
+
+    async fn stash_many<K, V, I>(
+        &self,
+        entries: I,
+    ) -> Result<(), StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+        I: IntoIterator<Item = (K, V)> + Send,
+        I::IntoIter: Send,
+    {
+        let entries: Vec<(String, String)> = entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        for (key, _) in &entries {
+            Self::validate_key(key)?;
+        }
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        let values: Vec<&str> = entries.iter().map(|(_, value)| value.as_str()).collect();
+        self.0.execute(
+            "INSERT INTO stash (key, value, expiry)
+            SELECT key, value, NULL::bigint FROM UNNEST($1::text[], $2::text[]) AS t(key, value)
+            ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expiry = NULL",
+            &[&keys, &values],
+        ).await?;
+        Ok(())
+    }
+
+    async fn delete_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<(), StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    {
+        let keys: Vec<String> = keys.into_iter().map(|key| key.as_ref().to_owned()).collect();
+        if keys.is_empty() {
+            return Ok(());
+        }
+        self.0.execute(
+            "DELETE FROM stash WHERE key = ANY($1)",
+            &[&keys],
+        ).await?;
+        Ok(())
+    }
+
+    async fn stash_with_ttl<K, V>(
+        &self,
+        key: K,
+        value: V,
+        ttl: Duration,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+    {
+        let key = Into::<String>::into(key);
+        Self::validate_key(&key)?;
+        let value = Into::<String>::into(value);
+        let now = Self::now_epoch();
+        // Round up to the next whole second rather than truncating: `expiry`
+        // is stored with one-second resolution, so truncating a sub-second
+        // `ttl` down to `0` would make the row expire immediately instead of
+        // after `ttl` has elapsed.
+        let expiry = now + ttl.as_secs_f64().ceil() as i64;
+        let row = self.0.query_one(
+            "WITH previous AS (
+                SELECT value FROM stash WHERE key = $1 AND (expiry IS NULL OR expiry > $4)
+            )
+            INSERT INTO stash (key, value, expiry) VALUES ($1, $2, $3)
+            ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expiry = EXCLUDED.expiry
+            RETURNING (SELECT value FROM previous)",
+            &[&key, &value, &expiry, &now],
+        ).await?;
+        Ok(row.get(0))
+    }
+
+    async fn ttl<K>(
+        &self,
+        key: K,
+    ) -> Result<Option<Duration>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+    {
+        let now = Self::now_epoch();
+        let row = self.0.query_opt(
+            "SELECT expiry - $2 FROM stash WHERE key = $1 AND expiry IS NOT NULL AND expiry > $2",
+            &[&key.as_ref(), &now],
+        ).await?;
+        Ok(row.map(|row| Duration::from_secs(row.get::<_, i64>(0) as u64)))
+    }
+
+    async fn keys_with_prefix<P>(
+        &self,
+        prefix: P,
+    ) -> Result<Vec<String>, StashError>
+    where
+        P: AsRef<str> + Send + Sync,
+    {
+        let prefix = prefix.as_ref();
+        Self::validate_prefix(prefix)?;
+        let pattern = Self::like_prefix_pattern(prefix);
+        let now = Self::now_epoch();
+        let rows = self.0.query(
+            "SELECT key FROM stash WHERE key LIKE $1 ESCAPE '\\' AND (expiry IS NULL OR expiry > $2)",
+            &[&pattern, &now],
+        ).await?;
+        Ok(rows.into_iter().map(|row| row.get(0)).collect())
+    }
+}
+
+// TODO: Add unit tests for Postgres stash using a test database
@@ -1,6 +1,14 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex as StdMutex,
+    },
+    time::{Duration, Instant},
+};
 
-use redis::{aio::MultiplexedConnection, AsyncCommands, Client};
+use redis::{aio::MultiplexedConnection, AsyncCommands, Client, ScanOptions, SetExpiry, SetOptions};
+use tokio::sync::RwLock;
 
 use crate::{Stash, StashError};
 
@@ -12,17 +20,195 @@ impl From<RedisError> for StashError {
     }
 }
 
+/// Returns `true` if `error` indicates the underlying connection is broken
+/// (as opposed to, say, a `WRONGTYPE` reply) and the connection should be
+/// rebuilt.
+#[inline]
+fn is_connection_error(error: &RedisError) -> bool {
+    error.is_io_error() || error.is_unrecoverable_error()
+}
+
 /// Contains Redis user credentials.
-/// 
+///
 /// This is typically used when calling [`RedisStash::connect`].
 pub struct RedisCredentials {
     pub username: String,
     pub password: String,
 }
 
+/// The initial delay used for the exponential backoff between reconnect
+/// attempts.
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(50);
+
+/// The maximum delay between reconnect attempts; the backoff doubles on
+/// every failed attempt up to this cap.
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// The number of reconnect attempts made before giving up.
+const RECONNECT_ATTEMPTS: u32 = 5;
+
+/// The number of consecutive connection failures (within
+/// [`BREAKER_FAILURE_WINDOW`]) that trip the [`CircuitBreaker`] open.
+const BREAKER_FAILURE_THRESHOLD: u32 = 5;
+
+/// The window within which [`BREAKER_FAILURE_THRESHOLD`] failures must occur
+/// to trip the breaker; failures older than this reset the streak.
+const BREAKER_FAILURE_WINDOW: Duration = Duration::from_secs(30);
+
+/// How long the [`CircuitBreaker`] stays open (failing fast) before allowing
+/// a single trial request through in the half-open state.
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// The `COUNT` hint used for each `SCAN` call issued by
+/// [`RedisStash::keys_with_prefix`].
+const SCAN_COUNT: usize = 100;
+
+/// The state of a [`CircuitBreaker`].
+enum BreakerState {
+    /// Calls are let through normally.
+    Closed,
+    /// Calls fail fast until `until` has elapsed.
+    Open { until: Instant },
+    /// A single trial call is allowed through to decide whether to close the
+    /// breaker again; further calls fail fast until the trial settles.
+    HalfOpen { trial_in_flight: bool },
+}
+
+/// Tracks consecutive backend connection failures and trips open to fail
+/// fast instead of hitting an already-broken connection on every call.
+struct CircuitBreaker {
+    state: StdMutex<BreakerState>,
+    consecutive_failures: AtomicU32,
+    streak_started_at: StdMutex<Option<Instant>>,
+}
+
+/// Returned by [`CircuitBreaker::acquire`]; call [`settle`](Self::settle)
+/// once the guarded call's outcome has been recorded with
+/// [`CircuitBreaker::on_success`]/[`CircuitBreaker::on_failure`].
+///
+/// Dropping this unsettled (e.g. because the caller's future was cancelled
+/// before the call resolved) releases any half-open trial permit it may be
+/// holding, rather than leaving the breaker stuck behind a trial that never
+/// reports back.
+struct TrialGuard<'a> {
+    breaker: &'a CircuitBreaker,
+    settled: bool,
+}
+
+impl TrialGuard<'_> {
+    /// Marks this guard as settled, so dropping it is a no-op.
+    fn settle(&mut self) {
+        self.settled = true;
+    }
+}
+
+impl Drop for TrialGuard<'_> {
+    fn drop(&mut self) {
+        if !self.settled {
+            self.breaker.release_unsettled_trial();
+        }
+    }
+}
+
+impl CircuitBreaker {
+    fn new() -> Self {
+        Self {
+            state: StdMutex::new(BreakerState::Closed),
+            consecutive_failures: AtomicU32::new(0),
+            streak_started_at: StdMutex::new(None),
+        }
+    }
+
+    /// Checks whether a call is currently allowed through, transitioning
+    /// `Open` to `HalfOpen` once the cooldown has elapsed.
+    ///
+    /// Returns a [`TrialGuard`] that must be [`settle`](TrialGuard::settle)d
+    /// once the call's outcome is known. If it's dropped unsettled instead
+    /// (e.g. the caller's future was cancelled, as with
+    /// `tokio::time::timeout`), the half-open trial permit it may be holding
+    /// is released so the breaker isn't stuck failing fast forever.
+    fn acquire(&self) -> Result<TrialGuard<'_>, StashError> {
+        let mut state = self.state.lock().unwrap();
+        match &mut *state {
+            BreakerState::Closed => Ok(()),
+            BreakerState::Open { until } => {
+                if Instant::now() >= *until {
+                    *state = BreakerState::HalfOpen { trial_in_flight: true };
+                    Ok(())
+                } else {
+                    Err(StashError::CircuitOpen)
+                }
+            },
+            BreakerState::HalfOpen { trial_in_flight } => {
+                if *trial_in_flight {
+                    Err(StashError::CircuitOpen)
+                } else {
+                    *trial_in_flight = true;
+                    Ok(())
+                }
+            },
+        }?;
+        Ok(TrialGuard { breaker: self, settled: false })
+    }
+
+    /// Releases a half-open trial permit left behind by a [`TrialGuard`]
+    /// that was dropped before its call settled. A no-op if the breaker
+    /// isn't currently half-open (e.g. it already moved on via
+    /// [`CircuitBreaker::on_success`]/[`CircuitBreaker::on_failure`]).
+    fn release_unsettled_trial(&self) {
+        if let BreakerState::HalfOpen { trial_in_flight } = &mut *self.state.lock().unwrap() {
+            *trial_in_flight = false;
+        }
+    }
+
+    /// Records a successful call, closing the breaker.
+    fn on_success(&self) {
+        *self.state.lock().unwrap() = BreakerState::Closed;
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        *self.streak_started_at.lock().unwrap() = None;
+    }
+
+    /// Records a connection failure, tripping the breaker open if the
+    /// failure threshold has been reached (or immediately, if this failure
+    /// was the half-open trial).
+    fn on_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        if matches!(&*state, BreakerState::HalfOpen { .. }) {
+            *state = BreakerState::Open { until: Instant::now() + BREAKER_COOLDOWN };
+            return;
+        }
+
+        let now = Instant::now();
+        let mut streak_started_at = self.streak_started_at.lock().unwrap();
+        let within_window = streak_started_at.is_some_and(|start| now.duration_since(start) <= BREAKER_FAILURE_WINDOW);
+        let failures = if within_window {
+            self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1
+        } else {
+            *streak_started_at = Some(now);
+            self.consecutive_failures.store(1, Ordering::Relaxed);
+            1
+        };
+
+        if failures >= BREAKER_FAILURE_THRESHOLD {
+            *state = BreakerState::Open { until: now + BREAKER_COOLDOWN };
+            self.consecutive_failures.store(0, Ordering::Relaxed);
+            *streak_started_at = None;
+        }
+    }
+}
+
 /// [`Stash`] connected to a Redis database.
+///
+/// If the connection drops, calls transparently rebuild it (with
+/// exponential backoff) rather than failing forever. A [`CircuitBreaker`]
+/// sits on top so that once reconnects keep failing, calls fail fast
+/// instead of repeatedly hitting a dead backend.
 #[derive(Clone)]
-pub struct RedisStash(MultiplexedConnection);
+pub struct RedisStash {
+    url: String,
+    connection: std::sync::Arc<RwLock<MultiplexedConnection>>,
+    breaker: std::sync::Arc<CircuitBreaker>,
+}
 
 impl RedisStash {
     /// Connects to a Redis server and returns a new [`RedisStash`].
@@ -66,25 +252,116 @@ impl RedisStash {
     /// Connects to a Redis server and returns a new [`RedisStash`].
     pub async fn connect_with_string<T: Into<String>>(connection_string: T) -> Result<Self, RedisError> {
         let url: String = connection_string.into();
+        let connection = Self::open_connection(&url).await?;
+        Ok(Self {
+            url,
+            connection: std::sync::Arc::new(RwLock::new(connection)),
+            breaker: std::sync::Arc::new(CircuitBreaker::new()),
+        })
+    }
+
+    /// Opens a fresh [`MultiplexedConnection`] to `url`.
+    async fn open_connection(url: &str) -> Result<MultiplexedConnection, RedisError> {
         let client = Client::open(url)?;
-        let connection = client.get_multiplexed_async_connection().await?;
-        Ok(Self(connection))
+        client.get_multiplexed_async_connection().await
     }
-}
 
-impl Deref for RedisStash {
-    type Target = MultiplexedConnection;
-    #[inline(always)]
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// Rebuilds the shared connection, retrying with exponential backoff.
+    async fn reconnect(&self) -> Result<(), RedisError> {
+        let mut delay = RECONNECT_INITIAL_DELAY;
+        let mut last_error = None;
+        for attempt in 0..RECONNECT_ATTEMPTS {
+            match Self::open_connection(&self.url).await {
+                Ok(connection) => {
+                    *self.connection.write().await = connection;
+                    return Ok(());
+                },
+                Err(error) => {
+                    last_error = Some(error);
+                    if attempt + 1 < RECONNECT_ATTEMPTS {
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+                    }
+                },
+            }
+        }
+        Err(last_error.expect("RECONNECT_ATTEMPTS is non-zero"))
+    }
+
+    /// Runs `operation` against the shared connection, going through the
+    /// [`CircuitBreaker`] and transparently reconnecting (then retrying
+    /// once) on a connection-level error.
+    async fn guarded<F, Fut, T>(&self, operation: F) -> Result<T, StashError>
+    where
+        F: Fn(MultiplexedConnection) -> Fut,
+        Fut: Future<Output = Result<T, RedisError>>,
+    {
+        let mut guard = self.breaker.acquire()?;
+
+        let connection = self.connection.read().await.clone();
+        match operation(connection).await {
+            Ok(value) => {
+                guard.settle();
+                self.breaker.on_success();
+                Ok(value)
+            },
+            Err(error) if is_connection_error(&error) => {
+                guard.settle();
+                self.breaker.on_failure();
+                if self.reconnect().await.is_err() {
+                    return Err(error.into());
+                }
+
+                let connection = self.connection.read().await.clone();
+                let result = operation(connection).await;
+                if result.is_ok() {
+                    self.breaker.on_success();
+                }
+                Ok(result?)
+            },
+            // Not a connection-level failure (e.g. a `WRONGTYPE` reply), so
+            // the breaker's failure/success bookkeeping is left alone; `guard`
+            // is dropped unsettled here, releasing a half-open trial permit
+            // (if this call was one) so the next call can try again.
+            Err(error) => Err(error.into()),
+        }
     }
 }
 
-impl DerefMut for RedisStash {
-    #[inline(always)]
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+/// Issues `SET key value GET`, returning the previous value (if there was
+/// one) rather than the plain `OK` a bare `SET` replies with.
+///
+/// Generic over the connection so it can be exercised against a fake
+/// [`redis::aio::ConnectionLike`] in tests without a real server.
+async fn set_and_get_previous<C>(
+    connection: &mut C,
+    key: String,
+    value: String,
+    expiration: Option<SetExpiry>,
+) -> Result<Option<String>, RedisError>
+where
+    C: redis::aio::ConnectionLike + Send,
+{
+    let mut options = SetOptions::default().get(true);
+    if let Some(expiration) = expiration {
+        options = options.with_expiration(expiration);
     }
+    connection.set_options(key, value, options).await
+}
+
+/// Issues `GETDEL key`, returning the deleted value (if there was one)
+/// rather than the count a bare `DEL` replies with.
+///
+/// Generic over the connection so it can be exercised against a fake
+/// [`redis::aio::ConnectionLike`] in tests without a real server.
+async fn delete_and_get_previous<C>(
+    connection: &mut C,
+    key: String,
+) -> Result<Option<String>, RedisError>
+where
+    C: redis::aio::ConnectionLike + Send,
+{
+    connection.get_del(key).await
 }
 
 #[async_trait::async_trait]
@@ -96,12 +373,11 @@ impl Stash for RedisStash {
     where
         K: AsRef<str> + Send + Sync,
     {
-        Ok(
-            self.0
-                .clone()
-                .get(key.as_ref())
-                .await?
-        )
+        let key = key.as_ref().to_owned();
+        self.guarded(move |mut connection| {
+            let key = key.clone();
+            async move { connection.get(key).await }
+        }).await
     }
 
     async fn stash<K, V>(
@@ -115,13 +391,12 @@ impl Stash for RedisStash {
     {
         let key = Into::<String>::into(key);
         Self::validate_key(&key)?;
-        let previous: Option<String> = self.0
-            .clone()
-            .set(
-                key,
-                Into::<String>::into(value)
-            ).await?;
-        Ok(previous)
+        let value = Into::<String>::into(value);
+        self.guarded(move |mut connection| {
+            let key = key.clone();
+            let value = value.clone();
+            async move { set_and_get_previous(&mut connection, key, value, None).await }
+        }).await
     }
 
     async fn delete<K>(
@@ -131,11 +406,309 @@ impl Stash for RedisStash {
     where
         K: AsRef<str> + Send + Sync,
     {
-        let previous: Option<String> = self.0
-            .clone()
-            .del(key.as_ref()).await?;
-        Ok(previous)
+        let key = key.as_ref().to_owned();
+        self.guarded(move |mut connection| {
+            let key = key.clone();
+            async move { delete_and_get_previous(&mut connection, key).await }
+        }).await
+    }
+
+    async fn fetch_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<Vec<Option<String>>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    {
+        let keys: Vec<String> = keys.into_iter().map(|key| key.as_ref().to_owned()).collect();
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.guarded(move |mut connection| {
+            let keys = keys.clone();
+            async move { connection.mget(keys).await }
+        }).await
+    }
+
+    async fn stash_many<K, V, I>(
+        &self,
+        entries: I,
+    ) -> Result<(), StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+        I: IntoIterator<Item = (K, V)> + Send,
+        I::IntoIter: Send,
+    {
+        let entries: Vec<(String, String)> = entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        for (key, _) in &entries {
+            Self::validate_key(key)?;
+        }
+        if entries.is_empty() {
+            return Ok(());
+        }
+        self.guarded(move |mut connection| {
+            let entries = entries.clone();
+            async move { connection.mset::<_, _, ()>(&entries).await }
+        }).await
+    }
+
+    async fn delete_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<(), StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    {
+        let keys: Vec<String> = keys.into_iter().map(|key| key.as_ref().to_owned()).collect();
+        if keys.is_empty() {
+            return Ok(());
+        }
+        self.guarded(move |mut connection| {
+            let keys = keys.clone();
+            async move { connection.del::<_, ()>(keys).await }
+        }).await
+    }
+
+    async fn stash_with_ttl<K, V>(
+        &self,
+        key: K,
+        value: V,
+        ttl: Duration,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+    {
+        let key = Into::<String>::into(key);
+        Self::validate_key(&key)?;
+        let value = Into::<String>::into(value);
+        // Use millisecond precision (`PX`) rather than truncating to whole
+        // seconds: `SET ... EX 0` is rejected by Redis as an invalid expire
+        // time, so a sub-second `ttl` would otherwise fail outright.
+        let milliseconds = ttl.as_millis().max(1) as u64;
+        self.guarded(move |mut connection| {
+            let key = key.clone();
+            let value = value.clone();
+            async move {
+                set_and_get_previous(&mut connection, key, value, Some(SetExpiry::PX(milliseconds))).await
+            }
+        }).await
+    }
+
+    async fn ttl<K>(
+        &self,
+        key: K,
+    ) -> Result<Option<Duration>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+    {
+        let key = key.as_ref().to_owned();
+        let seconds: i64 = self.guarded(move |mut connection| {
+            let key = key.clone();
+            async move { connection.ttl(key).await }
+        }).await?;
+        Ok(
+            if seconds < 0 {
+                None
+            } else {
+                Some(Duration::from_secs(seconds as u64))
+            }
+        )
+    }
+
+    async fn keys_with_prefix<P>(
+        &self,
+        prefix: P,
+    ) -> Result<Vec<String>, StashError>
+    where
+        P: AsRef<str> + Send + Sync,
+    {
+        let prefix = prefix.as_ref();
+        Self::validate_prefix(prefix)?;
+        let pattern = format!("{prefix}*");
+        self.guarded(move |mut connection| {
+            let pattern = pattern.clone();
+            async move {
+                let options = ScanOptions::default()
+                    .with_pattern(pattern)
+                    .with_count(SCAN_COUNT);
+                let mut iter: redis::AsyncIter<String> = connection.scan_options(options).await?;
+                let mut keys = Vec::new();
+                while let Some(key) = iter.next_item().await {
+                    keys.push(key);
+                }
+                Ok(keys)
+            }
+        }).await
     }
 }
 
-// TODO: Add unit tests for Redis stash using mock Redis server
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// A minimal in-memory fake of a Redis connection, just enough to
+    /// exercise [`set_and_get_previous`]/[`delete_and_get_previous`]'s
+    /// command construction and reply parsing without a real server.
+    #[derive(Default)]
+    struct FakeConnection {
+        store: HashMap<String, String>,
+    }
+
+    impl redis::aio::ConnectionLike for FakeConnection {
+        fn req_packed_command<'a>(&'a mut self, cmd: &'a redis::Cmd) -> redis::RedisFuture<'a, redis::Value> {
+            Box::pin(async move {
+                let args: Vec<Vec<u8>> = cmd.args_iter()
+                    .map(|arg| match arg {
+                        redis::Arg::Simple(bytes) => bytes.to_vec(),
+                        redis::Arg::Cursor => b"0".to_vec(),
+                    })
+                    .collect();
+                let name = String::from_utf8_lossy(&args[0]).to_ascii_uppercase();
+                match name.as_str() {
+                    "SET" => {
+                        let key = String::from_utf8_lossy(&args[1]).into_owned();
+                        let value = String::from_utf8_lossy(&args[2]).into_owned();
+                        let wants_previous = args[3..].iter().any(|arg| arg.eq_ignore_ascii_case(b"GET"));
+                        let previous = self.store.insert(key, value);
+                        Ok(match (wants_previous, previous) {
+                            (true, Some(previous)) => redis::Value::BulkString(previous.into_bytes()),
+                            (true, None) => redis::Value::Nil,
+                            (false, _) => redis::Value::Okay,
+                        })
+                    },
+                    "GETDEL" => {
+                        let key = String::from_utf8_lossy(&args[1]).into_owned();
+                        Ok(match self.store.remove(&key) {
+                            Some(previous) => redis::Value::BulkString(previous.into_bytes()),
+                            None => redis::Value::Nil,
+                        })
+                    },
+                    other => panic!("FakeConnection: unexpected command {other}"),
+                }
+            })
+        }
+
+        fn req_packed_commands<'a>(
+            &'a mut self,
+            _cmd: &'a redis::Pipeline,
+            _offset: usize,
+            _count: usize,
+        ) -> redis::RedisFuture<'a, Vec<redis::Value>> {
+            unimplemented!("FakeConnection does not support pipelining")
+        }
+
+        fn get_db(&self) -> i64 {
+            0
+        }
+    }
+
+    #[tokio::test]
+    async fn set_and_get_previous_returns_none_on_first_insert_then_the_previous_value() {
+        let mut connection = FakeConnection::default();
+        assert_eq!(
+            set_and_get_previous(&mut connection, "key".into(), "a".into(), None).await.unwrap(),
+            None,
+        );
+        assert_eq!(
+            set_and_get_previous(&mut connection, "key".into(), "b".into(), None).await.unwrap(),
+            Some("a".into()),
+        );
+        assert_eq!(connection.store.get("key"), Some(&"b".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn set_and_get_previous_with_expiration_still_returns_the_previous_value() {
+        let mut connection = FakeConnection::default();
+        set_and_get_previous(&mut connection, "key".into(), "a".into(), None).await.unwrap();
+        assert_eq!(
+            set_and_get_previous(&mut connection, "key".into(), "b".into(), Some(SetExpiry::PX(1000))).await.unwrap(),
+            Some("a".into()),
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_and_get_previous_returns_none_when_the_key_is_missing() {
+        let mut connection = FakeConnection::default();
+        assert_eq!(delete_and_get_previous(&mut connection, "key".into()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn delete_and_get_previous_returns_the_deleted_value_and_removes_the_key() {
+        let mut connection = FakeConnection::default();
+        set_and_get_previous(&mut connection, "key".into(), "a".into(), None).await.unwrap();
+        assert_eq!(
+            delete_and_get_previous(&mut connection, "key".into()).await.unwrap(),
+            Some("a".into()),
+        );
+        assert!(connection.store.is_empty());
+    }
+
+    #[test]
+    fn closed_breaker_allows_calls() {
+        let breaker = CircuitBreaker::new();
+        let mut guard = breaker.acquire().unwrap();
+        guard.settle();
+        breaker.on_success();
+    }
+
+    #[test]
+    fn trips_open_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new();
+        for _ in 0..BREAKER_FAILURE_THRESHOLD {
+            let mut guard = breaker.acquire().unwrap();
+            guard.settle();
+            breaker.on_failure();
+        }
+        assert!(matches!(breaker.acquire(), Err(StashError::CircuitOpen)));
+    }
+
+    #[test]
+    fn half_open_allows_only_a_single_trial() {
+        let breaker = CircuitBreaker::new();
+        *breaker.state.lock().unwrap() = BreakerState::HalfOpen { trial_in_flight: false };
+
+        let _trial = breaker.acquire().unwrap();
+        assert!(matches!(breaker.acquire(), Err(StashError::CircuitOpen)));
+    }
+
+    #[test]
+    fn dropping_an_unsettled_trial_releases_the_permit() {
+        let breaker = CircuitBreaker::new();
+        *breaker.state.lock().unwrap() = BreakerState::HalfOpen { trial_in_flight: false };
+
+        {
+            let _trial = breaker.acquire().unwrap();
+            assert!(matches!(breaker.acquire(), Err(StashError::CircuitOpen)));
+            // `_trial` is dropped here without being settled, simulating the
+            // guarded call's future being cancelled mid-flight (e.g. by a
+            // `tokio::time::timeout` around it) before `on_success`/
+            // `on_failure` could run.
+        }
+
+        // The permit must be released, not stuck behind a trial that never
+        // reported back.
+        assert!(breaker.acquire().is_ok());
+    }
+
+    #[test]
+    fn settled_trial_success_closes_the_breaker() {
+        let breaker = CircuitBreaker::new();
+        *breaker.state.lock().unwrap() = BreakerState::HalfOpen { trial_in_flight: false };
+
+        let mut guard = breaker.acquire().unwrap();
+        guard.settle();
+        breaker.on_success();
+
+        assert!(matches!(*breaker.state.lock().unwrap(), BreakerState::Closed));
+    }
+}
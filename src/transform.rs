@@ -0,0 +1,315 @@
+use std::{
+    io::{Read, Write},
+    sync::Arc,
+};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::Engine;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression as GzipLevel};
+
+use crate::{Stash, StashError};
+
+/// A single stage of a [`TransformStash`] pipeline.
+///
+/// Implementors transform a value on the way into the inner [`Stash`] (via
+/// [`Transform::encode`]) and reverse that transformation on the way out
+/// (via [`Transform::decode`]). Stages compose: [`TransformStash`] applies
+/// them in order on `stash`/`stash_with_ttl` and in reverse order on
+/// `fetch`/`delete`.
+pub trait Transform: Send + Sync {
+    /// Transforms `value` on its way into the inner [`Stash`].
+    fn encode(&self, value: &str) -> Result<String, StashError>;
+
+    /// Reverses [`Transform::encode`] on a value coming out of the inner
+    /// [`Stash`].
+    fn decode(&self, value: &str) -> Result<String, StashError>;
+}
+
+/// An AEAD-encrypting [`Transform`] using AES-256-GCM.
+///
+/// Each call to [`Transform::encode`] generates a fresh random nonce,
+/// prepends it to the ciphertext, and base64-encodes the result so it can
+/// be handed to a backend that only stores strings.
+pub struct AeadEncryption {
+    cipher: Aes256Gcm,
+}
+
+impl AeadEncryption {
+    /// Creates a new [`AeadEncryption`] transform from a 256-bit key.
+    #[must_use]
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        }
+    }
+}
+
+impl Transform for AeadEncryption {
+    fn encode(&self, value: &str) -> Result<String, StashError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher
+            .encrypt(&nonce, value.as_bytes())
+            .map_err(StashError::backend)?;
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(base64::engine::general_purpose::STANDARD.encode(payload))
+    }
+
+    fn decode(&self, value: &str) -> Result<String, StashError> {
+        let payload = base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .map_err(StashError::backend)?;
+        if payload.len() < 12 {
+            return Err(StashError::backend(aes_gcm::Error));
+        }
+        let (nonce, ciphertext) = payload.split_at(12);
+        let plaintext = self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(StashError::backend)?;
+        String::from_utf8(plaintext).map_err(StashError::backend)
+    }
+}
+
+/// A gzip-compressing [`Transform`].
+///
+/// Compressed bytes are base64-encoded so they can be handed to a backend
+/// that only stores strings.
+#[derive(Default)]
+pub struct GzipCompression {
+    level: GzipLevel,
+}
+
+impl Transform for GzipCompression {
+    fn encode(&self, value: &str) -> Result<String, StashError> {
+        let mut encoder = GzEncoder::new(Vec::new(), self.level);
+        encoder.write_all(value.as_bytes()).map_err(StashError::backend)?;
+        let compressed = encoder.finish().map_err(StashError::backend)?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(compressed))
+    }
+
+    fn decode(&self, value: &str) -> Result<String, StashError> {
+        let compressed = base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .map_err(StashError::backend)?;
+        let mut decompressed = String::new();
+        GzDecoder::new(&compressed[..])
+            .read_to_string(&mut decompressed)
+            .map_err(StashError::backend)?;
+        Ok(decompressed)
+    }
+}
+
+/// A [`Stash`] decorator that transparently transforms values on the way in
+/// and out of an inner [`Stash`], e.g. compressing then encrypting on
+/// `stash`, and reversing that on `fetch`. Keys pass through unchanged, so
+/// namespacing (and [`Stash::keys_with_prefix`]) still works.
+///
+/// This gives at-rest confidentiality for any inner [`Stash`] (e.g.
+/// [`RedisStash`](crate::RedisStash)) without the backend ever seeing
+/// plaintext.
+#[derive(Clone)]
+pub struct TransformStash<S: Stash + Clone> {
+    inner: S,
+    pipeline: Arc<[Box<dyn Transform>]>,
+}
+
+impl<S: Stash + Clone> TransformStash<S> {
+    /// Wraps `inner` with a `pipeline` of [`Transform`] stages, applied in
+    /// order on the way in and in reverse order on the way out.
+    #[must_use]
+    pub fn new(inner: S, pipeline: Vec<Box<dyn Transform>>) -> Self {
+        Self { inner, pipeline: pipeline.into() }
+    }
+
+    fn encode(&self, value: &str) -> Result<String, StashError> {
+        self.pipeline.iter().try_fold(value.to_owned(), |value, stage| stage.encode(&value))
+    }
+
+    fn decode(&self, value: &str) -> Result<String, StashError> {
+        self.pipeline.iter().rev().try_fold(value.to_owned(), |value, stage| stage.decode(&value))
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: Stash + Clone> Stash for TransformStash<S> {
+    async fn fetch<K>(
+        &self,
+        key: K,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+    {
+        match self.inner.fetch(key).await? {
+            Some(value) => Ok(Some(self.decode(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn stash<K, V>(
+        &self,
+        key: K,
+        value: V,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+    {
+        let value = self.encode(&Into::<String>::into(value))?;
+        // The write already succeeded by this point (e.g. against the inner
+        // stash), so a previous value that fails to decode (most likely
+        // because it was encrypted under a key that has since been rotated)
+        // must not turn the whole call into an error.
+        Ok(self.inner.stash(key, value).await?.and_then(|previous| self.decode(&previous).ok()))
+    }
+
+    async fn delete<K>(
+        &self,
+        key: K,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+    {
+        // See the comment in `stash`: the delete already succeeded, so a
+        // previous value that fails to decode must not fail the call.
+        Ok(self.inner.delete(key).await?.and_then(|previous| self.decode(&previous).ok()))
+    }
+
+    async fn fetch_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<Vec<Option<String>>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    {
+        self.inner.fetch_many(keys).await?
+            .into_iter()
+            .map(|value| value.map(|value| self.decode(&value)).transpose())
+            .collect()
+    }
+
+    async fn stash_many<K, V, I>(
+        &self,
+        entries: I,
+    ) -> Result<(), StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+        I: IntoIterator<Item = (K, V)> + Send,
+        I::IntoIter: Send,
+    {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| Ok((key.into(), self.encode(&value.into())?)))
+            .collect::<Result<Vec<(String, String)>, StashError>>()?;
+        self.inner.stash_many(entries).await
+    }
+
+    async fn delete_many<K, I>(
+        &self,
+        keys: I,
+    ) -> Result<(), StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+        I: IntoIterator<Item = K> + Send,
+        I::IntoIter: Send,
+    {
+        self.inner.delete_many(keys).await
+    }
+
+    async fn stash_with_ttl<K, V>(
+        &self,
+        key: K,
+        value: V,
+        ttl: std::time::Duration,
+    ) -> Result<Option<String>, StashError>
+    where
+        K: Into<String> + Send + Sync,
+        V: Into<String> + Send + Sync,
+    {
+        let value = self.encode(&Into::<String>::into(value))?;
+        // See the comment in `stash`: the write already succeeded, so a
+        // previous value that fails to decode must not fail the call.
+        Ok(self.inner.stash_with_ttl(key, value, ttl).await?.and_then(|previous| self.decode(&previous).ok()))
+    }
+
+    async fn ttl<K>(
+        &self,
+        key: K,
+    ) -> Result<Option<std::time::Duration>, StashError>
+    where
+        K: AsRef<str> + Send + Sync,
+    {
+        self.inner.ttl(key).await
+    }
+
+    async fn keys_with_prefix<P>(
+        &self,
+        prefix: P,
+    ) -> Result<Vec<String>, StashError>
+    where
+        P: AsRef<str> + Send + Sync,
+    {
+        self.inner.keys_with_prefix(prefix).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalStash;
+
+    #[test]
+    fn aead_encryption_round_trips() {
+        let transform = AeadEncryption::new(&[7u8; 32]);
+        let encoded = transform.encode("hello world").unwrap();
+        assert_ne!(encoded, "hello world");
+        assert_eq!(transform.decode(&encoded).unwrap(), "hello world");
+    }
+
+    #[test]
+    fn gzip_compression_round_trips() {
+        let transform = GzipCompression::default();
+        let encoded = transform.encode("hello world").unwrap();
+        assert_eq!(transform.decode(&encoded).unwrap(), "hello world");
+    }
+
+    #[tokio::test]
+    async fn transform_stash_round_trips_through_pipeline() {
+        let stash = TransformStash::new(
+            LocalStash::new(),
+            vec![
+                Box::new(GzipCompression::default()),
+                Box::new(AeadEncryption::new(&[3u8; 32])),
+            ],
+        );
+        assert_eq!(stash.stash("user:1:name", "Alice").await.unwrap(), None);
+        assert_eq!(stash.fetch("user:1:name").await.unwrap(), Some("Alice".into()));
+        assert_eq!(
+            stash.stash("user:1:name", "Bob").await.unwrap(),
+            Some("Alice".into()),
+        );
+        assert_eq!(
+            stash.delete("user:1:name").await.unwrap(),
+            Some("Bob".into()),
+        );
+        assert_eq!(stash.fetch("user:1:name").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn stash_after_key_rotation_still_succeeds() {
+        let inner = LocalStash::new();
+        let before_rotation = TransformStash::new(inner.clone(), vec![Box::new(AeadEncryption::new(&[1u8; 32]))]);
+        assert_eq!(before_rotation.stash("user:1:name", "Alice").await.unwrap(), None);
+
+        let after_rotation = TransformStash::new(inner, vec![Box::new(AeadEncryption::new(&[2u8; 32]))]);
+        // The previous value was encrypted under the old key, so it can't be
+        // decoded under the new one — but the overwrite must still succeed.
+        assert_eq!(after_rotation.stash("user:1:name", "Bob").await.unwrap(), None);
+        assert_eq!(after_rotation.fetch("user:1:name").await.unwrap(), Some("Bob".into()));
+    }
+}